@@ -21,25 +21,80 @@ use log::{self, LogLevelFilter, Log, LogMetadata, LogRecord, SetLoggerError};
 
 use super::logger;
 
+const ONE_MINUTE_SECONDS: u64 = 60;
+const ONE_HOUR_SECONDS: u64 = 60 * 60;
 const ONE_DAY_SECONDS: u64 = 60 * 60 * 24;
 const NANOSECONDS_PER_MILLISECOND: i32 = 1_000_000;
 
+/// How often the log file is rotated.
+#[derive(Clone, Copy)]
+pub enum Rotation {
+    Minutely,
+    Hourly,
+    Daily,
+}
+
+impl Rotation {
+    // The strftime pattern used for the rotated archive's suffix. It must be
+    // fine-grained enough that two rollovers in the same period never
+    // collide on `fs::rename`.
+    fn archive_suffix_format(&self) -> &'static str {
+        match *self {
+            Rotation::Minutely => "%Y%m%d%H%M",
+            Rotation::Hourly => "%Y%m%d%H",
+            Rotation::Daily => "%Y%m%d",
+        }
+    }
+}
+
 fn systemtime_to_tm(t: SystemTime) -> Tm {
     let duration = t.duration_since(UNIX_EPOCH).unwrap();
     let spec = Timespec::new(duration.as_secs() as i64, duration.subsec_nanos() as i32);
     time::at(spec)
 }
 
-fn compute_rollover_time(tm: Tm) -> Tm {
-    let day_start_tm = Tm {
-        tm_hour: 0,
-        tm_min: 0,
-        tm_sec: 0,
-        tm_nsec: 0,
-        ..tm
-    };
-    let duration = time::Duration::from_std(Duration::new(ONE_DAY_SECONDS, 0)).unwrap();
-    (day_start_tm.to_utc() + duration).to_local()
+// Truncates `tm` down to the start of its rotation period, e.g. the top of
+// the minute/hour, or midnight for daily rotation.
+fn period_start(tm: Tm, rotation: Rotation) -> Tm {
+    match rotation {
+        Rotation::Minutely => {
+            Tm {
+                tm_sec: 0,
+                tm_nsec: 0,
+                ..tm
+            }
+        }
+        Rotation::Hourly => {
+            Tm {
+                tm_min: 0,
+                tm_sec: 0,
+                tm_nsec: 0,
+                ..tm
+            }
+        }
+        Rotation::Daily => {
+            Tm {
+                tm_hour: 0,
+                tm_min: 0,
+                tm_sec: 0,
+                tm_nsec: 0,
+                ..tm
+            }
+        }
+    }
+}
+
+fn period_seconds(rotation: Rotation) -> u64 {
+    match rotation {
+        Rotation::Minutely => ONE_MINUTE_SECONDS,
+        Rotation::Hourly => ONE_HOUR_SECONDS,
+        Rotation::Daily => ONE_DAY_SECONDS,
+    }
+}
+
+fn compute_rollover_time(tm: Tm, rotation: Rotation) -> Tm {
+    let duration = time::Duration::from_std(Duration::new(period_seconds(rotation), 0)).unwrap();
+    (period_start(tm, rotation).to_utc() + duration).to_local()
 }
 
 fn open_log_file(path: &str) -> io::Result<File> {
@@ -54,22 +109,80 @@ fn open_log_file(path: &str) -> io::Result<File> {
         .open(path)
 }
 
+// Parses an archive suffix of the form `STAMP` or `STAMP.N` (the part after
+// `<file_path>.`, where STAMP is any run of ASCII digits, e.g. `YYYYMMDD` or
+// `YYYYMMDDHH`) into a sort key of (stamp string, disambiguating index),
+// returning `None` for anything that doesn't match.
+fn parse_archive_suffix(suffix: &str) -> Option<(String, u64)> {
+    let mut parts = suffix.splitn(2, '.');
+    let date_part = parts.next().unwrap_or("");
+    if date_part.is_empty() || !date_part.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    match parts.next() {
+        Some(idx_str) => {
+            idx_str.parse::<u64>().ok().map(|idx| (date_part.to_string(), idx))
+        }
+        None => Some((date_part.to_string(), 0)),
+    }
+}
+
+// Abstracts over "what time is it" so the core's rollover logic can be
+// driven deterministically in tests instead of mutating file mtimes.
+enum Clock {
+    Default,
+    #[cfg(test)]
+    Manual(Mutex<Tm>),
+}
+
+impl Clock {
+    fn now(&self) -> Tm {
+        match *self {
+            Clock::Default => time::now(),
+            #[cfg(test)]
+            Clock::Manual(ref tm) => *tm.lock().unwrap(),
+        }
+    }
+
+    #[cfg(test)]
+    fn set_now(&self, now: Tm) {
+        match *self {
+            Clock::Manual(ref tm) => *tm.lock().unwrap() = now,
+            Clock::Default => unreachable!("the default clock cannot be set"),
+        }
+    }
+}
+
 struct RotatingFileLoggerCore {
     rollover_time: Tm,
     file_path: String,
     file: File,
+    file_size: u64,
+    max_size: Option<u64>,
+    max_files: Option<usize>,
+    rotation: Rotation,
+    clock: Clock,
 }
 
 impl RotatingFileLoggerCore {
-    fn new(path: &str) -> io::Result<RotatingFileLoggerCore> {
+    fn new(path: &str,
+           max_size: Option<u64>,
+           max_files: Option<usize>,
+           rotation: Rotation)
+           -> io::Result<RotatingFileLoggerCore> {
         let file = try!(open_log_file(path));
         let file_attr = fs::metadata(path).unwrap();
         let file_modified_time = file_attr.modified().unwrap();
-        let rollover_time = compute_rollover_time(systemtime_to_tm(file_modified_time));
+        let rollover_time = compute_rollover_time(systemtime_to_tm(file_modified_time), rotation);
         let ret = RotatingFileLoggerCore {
             rollover_time: rollover_time,
             file_path: path.to_string(),
             file: file,
+            clock: Clock::Default,
+            file_size: file_attr.len(),
+            max_size: max_size,
+            max_files: max_files,
+            rotation: rotation,
         };
         Ok(ret)
     }
@@ -78,23 +191,115 @@ impl RotatingFileLoggerCore {
         self.file = open_log_file(&self.file_path).unwrap()
     }
 
+    #[cfg(test)]
+    fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+
     fn should_rollover(&mut self) -> bool {
-        time::now() > self.rollover_time
+        if self.clock.now() > self.rollover_time {
+            return true;
+        }
+        match self.max_size {
+            Some(max_size) => self.file_size >= max_size,
+            None => false,
+        }
+    }
+
+    // Picks an archive path that doesn't already exist, appending a `.N`
+    // disambiguator when a rollover lands on a period that already has an
+    // archive. The suffix is stamped with the start of the period containing
+    // the current time. For a size-triggered rollover, which fires before
+    // `rollover_time` is reached, that is the period being closed; for a
+    // time-triggered rollover just past `rollover_time`, it is the period
+    // that `rollover_time` begins.
+    fn next_archive_path(&self) -> String {
+        let current_period = period_start(self.clock.now(), self.rotation);
+        let base = format!("{}.{}",
+                            self.file_path,
+                            time::strftime(self.rotation.archive_suffix_format(), &current_period)
+                                .unwrap());
+        if !Path::new(&base).exists() {
+            return base;
+        }
+        let mut i = 1;
+        loop {
+            let candidate = format!("{}.{}", base, i);
+            if !Path::new(&candidate).exists() {
+                return candidate;
+            }
+            i += 1;
+        }
     }
 
     fn do_rollover(&mut self) {
         self.close();
-        let mut s = self.file_path.clone();
-        s.push_str(".");
-        s.push_str(&time::strftime("%Y%m%d", &self.rollover_time).unwrap());
+        let s = self.next_archive_path();
         fs::rename(&self.file_path, &s).unwrap();
         self.update_rollover_time();
-        self.open()
+        self.file_size = 0;
+        self.open();
+        self.prune_archives();
+    }
+
+    // Deletes the oldest rotated archives in excess of `max_files`. Files in
+    // the log directory that don't match the `<file_path>.STAMP[.N]` pattern
+    // (STAMP being any run of digits, see `parse_archive_suffix`) are left
+    // alone.
+    fn prune_archives(&self) {
+        let max_files = match self.max_files {
+            Some(n) => n,
+            None => return,
+        };
+        let path = Path::new(&self.file_path);
+        let dir = match path.parent() {
+            Some(d) if !d.as_os_str().is_empty() => d,
+            _ => Path::new("."),
+        };
+        let file_name = match path.file_name().and_then(|n| n.to_str()) {
+            Some(n) => n,
+            None => return,
+        };
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                let _ = write!(io::stderr(), "failed to read log dir {:?}: {}\n", dir, e);
+                return;
+            }
+        };
+        let prefix = format!("{}.", file_name);
+        let mut archives = Vec::new();
+        for entry in entries {
+            let entry = match entry {
+                Ok(e) => e,
+                Err(_) => continue,
+            };
+            let name = match entry.file_name().into_string() {
+                Ok(n) => n,
+                Err(_) => continue,
+            };
+            if !name.starts_with(&prefix) {
+                continue;
+            }
+            if let Some(key) = parse_archive_suffix(&name[prefix.len()..]) {
+                archives.push((key, entry.path()));
+            }
+        }
+        if archives.len() <= max_files {
+            return;
+        }
+        archives.sort_by(|a, b| a.0.cmp(&b.0));
+        let remove_count = archives.len() - max_files;
+        for &(_, ref path) in &archives[..remove_count] {
+            if let Err(e) = fs::remove_file(path) {
+                let _ = write!(io::stderr(), "failed to remove old log archive {:?}: {}\n", path, e);
+            }
+        }
     }
 
     fn update_rollover_time(&mut self) {
-        let now = time::now();
-        self.rollover_time = compute_rollover_time(now);
+        let now = self.clock.now();
+        self.rollover_time = compute_rollover_time(now, self.rotation);
     }
 
     fn close(&mut self) {
@@ -102,26 +307,115 @@ impl RotatingFileLoggerCore {
     }
 }
 
-pub fn init(level: &str, file_path: &str) -> Result<(), SetLoggerError> {
+/// Renders a single log record into the bytes written to the log file.
+/// Keeping this behind a trait lets `RotatingFileLogger::log` stay
+/// independent of the on-disk representation.
+pub trait LogFormat: Send + Sync {
+    fn format(&self, record: &LogRecord, now: Tm) -> String;
+}
+
+fn file_name_of(record: &LogRecord) -> &str {
+    record.location().file().rsplit('/').nth(0).unwrap()
+}
+
+/// The original `"{ts},{ms} {file}:{line} - {level:5} - {args}"` layout.
+struct TextFormat;
+
+impl LogFormat for TextFormat {
+    fn format(&self, record: &LogRecord, now: Tm) -> String {
+        format!("{},{:03} {}:{} - {:5} - {}\n",
+                time::strftime("%Y-%m-%d %H:%M:%S", &now).unwrap(),
+                now.tm_nsec / NANOSECONDS_PER_MILLISECOND,
+                file_name_of(record),
+                record.location().line(),
+                record.level(),
+                record.args())
+    }
+}
+
+/// One JSON object per line, for easy ingestion by downstream collectors.
+struct JsonFormat;
+
+impl LogFormat for JsonFormat {
+    fn format(&self, record: &LogRecord, now: Tm) -> String {
+        format!("{{\"time\":\"{},{:03}\",\"level\":\"{}\",\"file\":\"{}\",\"line\":{},\"message\"\
+                 :\"{}\"}}\n",
+                time::strftime("%Y-%m-%d %H:%M:%S", &now).unwrap(),
+                now.tm_nsec / NANOSECONDS_PER_MILLISECOND,
+                record.level(),
+                escape_json_string(file_name_of(record)),
+                record.location().line(),
+                escape_json_string(&record.args().to_string()))
+    }
+}
+
+fn escape_json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// The built-in log line layouts selectable through `init`.
+#[derive(Clone, Copy)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl Format {
+    fn formatter(&self) -> Box<LogFormat> {
+        match *self {
+            Format::Text => Box::new(TextFormat),
+            Format::Json => Box::new(JsonFormat),
+        }
+    }
+}
+
+pub fn init(level: &str,
+            file_path: &str,
+            max_size: Option<u64>,
+            max_files: Option<usize>,
+            rotation: Rotation,
+            format: Format)
+            -> Result<(), SetLoggerError> {
     let l = logger::get_level_by_string(level);
     log::set_logger(|max_log_level| {
         max_log_level.set(l);
-        Box::new(RotatingFileLogger::new(level, file_path).unwrap())
+        Box::new(RotatingFileLogger::new(level, file_path, max_size, max_files, rotation, format)
+            .unwrap())
     })
 }
 
-/// A log implemetation which writes to file and rotates by day.
+/// A log implemetation which writes to file and rotates on a configurable period.
 pub struct RotatingFileLogger {
     level: LogLevelFilter,
     core: Mutex<RotatingFileLoggerCore>,
+    format: Box<LogFormat>,
 }
 
 impl RotatingFileLogger {
-    pub fn new(level: &str, file_path: &str) -> io::Result<RotatingFileLogger> {
-        let core = try!(RotatingFileLoggerCore::new(file_path));
+    pub fn new(level: &str,
+               file_path: &str,
+               max_size: Option<u64>,
+               max_files: Option<usize>,
+               rotation: Rotation,
+               format: Format)
+               -> io::Result<RotatingFileLogger> {
+        let core = try!(RotatingFileLoggerCore::new(file_path, max_size, max_files, rotation));
         let ret = RotatingFileLogger {
             level: logger::get_level_by_string(level),
             core: Mutex::new(core),
+            format: format.formatter(),
         };
         Ok(ret)
     }
@@ -138,15 +432,11 @@ impl Log for RotatingFileLogger {
             if core.should_rollover() {
                 core.do_rollover()
             };
-            let now = time::now();
-            let _ = write!(core.file,
-                           "{},{:03} {}:{} - {:5} - {}\n",
-                           time::strftime("%Y-%m-%d %H:%M:%S", &now).unwrap(),
-                           now.tm_nsec / NANOSECONDS_PER_MILLISECOND,
-                           record.location().file().rsplit('/').nth(0).unwrap(),
-                           record.location().line(),
-                           record.level(),
-                           record.args());
+            let now = core.clock.now();
+            let line = self.format.format(record, now);
+            if core.file.write_all(line.as_bytes()).is_ok() {
+                core.file_size += line.len() as u64;
+            }
         }
     }
 }
@@ -163,47 +453,220 @@ impl Drop for RotatingFileLogger {
 mod tests {
     extern crate log;
     extern crate rand;
-    extern crate utime;
-    use time::{self, Timespec};
+    use std::sync::Mutex;
+    use time;
     use std::io::prelude::*;
-    use std::fs::OpenOptions;
+    use std::fs::{self, OpenOptions};
     use std::path::Path;
     use tempdir::TempDir;
-    use super::{RotatingFileLoggerCore, ONE_DAY_SECONDS};
+    use super::{Clock, Rotation, RotatingFileLoggerCore, ONE_DAY_SECONDS, ONE_HOUR_SECONDS,
+                ONE_MINUTE_SECONDS};
 
     fn file_exists(file: &str) -> bool {
         let path = Path::new(file);
         path.exists() && path.is_file()
     }
 
+    fn add(tm: time::Tm, duration: time::Duration) -> time::Tm {
+        (tm.to_utc() + duration).to_local()
+    }
+
     #[test]
     fn test_rotating_file_logger() {
         let tmp_dir = TempDir::new("").unwrap();
         let log_file =
             tmp_dir.path().join("test_rotating_file_logger.log").to_str().unwrap().to_string();
-        // create a file with mtime == one day ago
+        OpenOptions::new().append(true).create(true).open(&log_file).unwrap();
+
+        let mut core = RotatingFileLoggerCore::new(&log_file, None, None, Rotation::Daily).unwrap();
+        let rollover_time = core.rollover_time;
+        core.set_clock(Clock::Manual(Mutex::new(rollover_time)));
+        assert!(!core.should_rollover());
+
+        // advance the manual clock just past the day boundary
+        core.clock.set_now(add(rollover_time, time::Duration::seconds(1)));
+        assert!(core.should_rollover());
+        core.do_rollover();
+
+        // the archive is stamped with the day that just began, i.e.
+        // `rollover_time`'s date, not the day before it
+        let mut rotated_file = log_file.clone();
+        rotated_file.push_str(".");
+        rotated_file.push_str(&time::strftime("%Y%m%d", &rollover_time).unwrap());
+        assert!(file_exists(&rotated_file));
+        assert!(!core.should_rollover());
+    }
+
+    #[test]
+    fn test_should_rollover_boundary() {
+        let tmp_dir = TempDir::new("").unwrap();
+        let log_file = tmp_dir.path().join("test_boundary.log").to_str().unwrap().to_string();
+        OpenOptions::new().append(true).create(true).open(&log_file).unwrap();
+
+        let mut core = RotatingFileLoggerCore::new(&log_file, None, None, Rotation::Daily).unwrap();
+        let rollover_time = core.rollover_time;
+        core.set_clock(Clock::Manual(Mutex::new(rollover_time)));
+        // exactly at the boundary is not yet a rollover
+        assert!(!core.should_rollover());
+        core.clock.set_now(add(rollover_time, time::Duration::seconds(1)));
+        assert!(core.should_rollover());
+    }
+
+    #[test]
+    fn test_consecutive_rollovers_with_manual_clock() {
+        let tmp_dir = TempDir::new("").unwrap();
+        let log_file = tmp_dir.path()
+            .join("test_consecutive_rollovers.log")
+            .to_str()
+            .unwrap()
+            .to_string();
+        OpenOptions::new().append(true).create(true).open(&log_file).unwrap();
+
+        let mut core = RotatingFileLoggerCore::new(&log_file, None, None, Rotation::Daily).unwrap();
+        core.set_clock(Clock::Manual(Mutex::new(core.rollover_time)));
+        let one_day = time::Duration::seconds(ONE_DAY_SECONDS as i64);
+
+        for _ in 0..3 {
+            let next = add(core.rollover_time, one_day);
+            core.clock.set_now(next);
+            assert!(core.should_rollover());
+            core.do_rollover();
+            assert!(!core.should_rollover());
+        }
+    }
+
+    #[test]
+    fn test_max_size_rollover() {
+        let tmp_dir = TempDir::new("").unwrap();
+        let log_file =
+            tmp_dir.path().join("test_max_size_rollover.log").to_str().unwrap().to_string();
         {
             let mut file = OpenOptions::new()
                 .append(true)
                 .create(true)
                 .open(&log_file)
                 .unwrap();
-            file.write_all(b"hello world!").unwrap();
-        }
-        let ts = time::now().to_timespec();
-        let one_day_ago = Timespec::new(ts.sec - ONE_DAY_SECONDS as i64, ts.nsec);
-        let rollover_time = super::compute_rollover_time(time::at(one_day_ago));
-        let time_in_sec = one_day_ago.sec as u64;
-        utime::set_file_times(&log_file, time_in_sec, time_in_sec).unwrap();
-        // initialize the logger
-        let mut core = RotatingFileLoggerCore::new(&log_file).unwrap();
+            file.write_all(b"012345678").unwrap();
+        }
+        let mut core = RotatingFileLoggerCore::new(&log_file, Some(10), None, Rotation::Daily).unwrap();
+        assert!(!core.should_rollover());
+        core.file_size += 1;
         assert!(core.should_rollover());
         core.do_rollover();
-        // check the rotated file exist
-        let mut rotated_file = log_file.clone();
-        rotated_file.push_str(".");
-        rotated_file.push_str(&time::strftime("%Y%m%d", &rollover_time).unwrap());
-        assert!(file_exists(&rotated_file));
-        assert!(!core.should_rollover());
+        assert_eq!(core.file_size, 0);
+        // a second same-day rollover must pick a disambiguated archive name
+        core.file_size = 11;
+        assert!(core.should_rollover());
+        core.do_rollover();
+        // computed independently of the core's internals -- both rollovers
+        // above happened today, so today's date is what must be stamped
+        let today = time::strftime("%Y%m%d", &time::now()).unwrap();
+        let mut first_archive = log_file.clone();
+        first_archive.push_str(".");
+        first_archive.push_str(&today);
+        let mut second_archive = first_archive.clone();
+        second_archive.push_str(".1");
+        assert!(file_exists(&first_archive));
+        assert!(file_exists(&second_archive));
+    }
+
+    #[test]
+    fn test_max_files_retention() {
+        let tmp_dir = TempDir::new("").unwrap();
+        let log_file =
+            tmp_dir.path().join("test_max_files_retention.log").to_str().unwrap().to_string();
+        OpenOptions::new().append(true).create(true).open(&log_file).unwrap();
+        // an unrelated file that happens to share the log's directory
+        let mut noise = log_file.clone();
+        noise.push_str(".backup");
+        OpenOptions::new().append(true).create(true).open(&noise).unwrap();
+
+        let mut core = RotatingFileLoggerCore::new(&log_file, Some(0), Some(2), Rotation::Daily).unwrap();
+        // computed independently of the core's internals -- all four
+        // rollovers below happen today, so today's date is what must be
+        // stamped on every archive
+        let date_suffix = time::strftime("%Y%m%d", &time::now()).unwrap();
+        for _ in 0..4 {
+            core.file_size = 1;
+            assert!(core.should_rollover());
+            core.do_rollover();
+        }
+
+        // the first two rollovers' archives (no index, then `.1`) are the
+        // oldest and must have been pruned away...
+        let mut oldest = log_file.clone();
+        oldest.push_str(".");
+        oldest.push_str(&date_suffix);
+        let mut second_oldest = oldest.clone();
+        second_oldest.push_str(".1");
+        assert!(!file_exists(&oldest));
+        assert!(!file_exists(&second_oldest));
+
+        // ...while the last two rollovers' archives (`.2` and `.3`) are the
+        // newest and must survive.
+        let mut third = oldest.clone();
+        third.push_str(".2");
+        let mut newest = oldest.clone();
+        newest.push_str(".3");
+        assert!(file_exists(&third));
+        assert!(file_exists(&newest));
+
+        let prefix = "test_max_files_retention.log.";
+        let archive_count = fs::read_dir(tmp_dir.path())
+            .unwrap()
+            .filter(|e| {
+                let name = e.as_ref().unwrap().file_name().into_string().unwrap();
+                name.starts_with(prefix) &&
+                name[prefix.len()..].chars().all(|c| c.is_ascii_digit() || c == '.')
+            })
+            .count();
+        assert_eq!(archive_count, 2);
+        assert!(file_exists(&noise));
+    }
+
+    #[test]
+    fn test_hourly_and_minutely_rotation() {
+        let tmp_dir = TempDir::new("").unwrap();
+
+        let hourly_log = tmp_dir.path().join("hourly.log").to_str().unwrap().to_string();
+        OpenOptions::new().append(true).create(true).open(&hourly_log).unwrap();
+        let mut hourly = RotatingFileLoggerCore::new(&hourly_log, None, None, Rotation::Hourly)
+            .unwrap();
+        hourly.set_clock(Clock::Manual(Mutex::new(hourly.rollover_time)));
+        let hourly_rollover_time = hourly.rollover_time;
+        // advance the manual clock just past the hour boundary
+        hourly.clock.set_now(add(hourly_rollover_time, time::Duration::seconds(1)));
+        assert!(hourly.should_rollover());
+        hourly.do_rollover();
+        let mut hourly_archive = hourly_log.clone();
+        hourly_archive.push_str(".");
+        // the archive is stamped with the hour that just began, i.e.
+        // `hourly_rollover_time`'s hour, not the hour before it
+        hourly_archive.push_str(&time::strftime("%Y%m%d%H", &hourly_rollover_time).unwrap());
+        assert!(file_exists(&hourly_archive));
+
+        let minutely_log = tmp_dir.path().join("minutely.log").to_str().unwrap().to_string();
+        OpenOptions::new().append(true).create(true).open(&minutely_log).unwrap();
+        let mut minutely = RotatingFileLoggerCore::new(&minutely_log, None, None, Rotation::Minutely)
+            .unwrap();
+        minutely.set_clock(Clock::Manual(Mutex::new(minutely.rollover_time)));
+        let minutely_rollover_time = minutely.rollover_time;
+        // advance the manual clock just past the minute boundary
+        minutely.clock.set_now(add(minutely_rollover_time, time::Duration::seconds(1)));
+        assert!(minutely.should_rollover());
+        minutely.do_rollover();
+        let mut minutely_archive = minutely_log.clone();
+        minutely_archive.push_str(".");
+        // the archive is stamped with the minute that just began, i.e.
+        // `minutely_rollover_time`'s minute, not the minute before it
+        minutely_archive.push_str(&time::strftime("%Y%m%d%H%M", &minutely_rollover_time).unwrap());
+        assert!(file_exists(&minutely_archive));
+    }
+
+    #[test]
+    fn test_escape_json_string() {
+        assert_eq!(super::escape_json_string("no special chars"), "no special chars");
+        assert_eq!(super::escape_json_string("quote\" and \\backslash\nline"),
+                   "quote\\\" and \\\\backslash\\nline");
     }
 }